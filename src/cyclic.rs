@@ -0,0 +1,372 @@
+use std::fmt;
+
+use crate::hex::{Endianness, HexString};
+
+// Alphabet used when a caller doesn't supply their own, matching the set
+// `pwntools` defaults to for `cyclic`/`cyclic_find`.
+pub const DEFAULT_ALPHABET: &str =
+    "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+// Default subsequence length: large enough that a 4-byte register value is
+// unlikely to alias another position in the pattern.
+pub const DEFAULT_N: usize = 4;
+
+// Upper bound on how many characters of a B(k, n) sequence `cyclic_find` will
+// generate while searching for a value. Without this, a representable but
+// huge `k^n` (e.g. the default 62-character alphabet with n = 5 is already
+// ~9.2e8) would hang or exhaust memory rather than failing cleanly.
+const MAX_SEARCHABLE_LENGTH: usize = 1 << 24;
+
+// Upper bound on `n` itself, independent of the alphabet size. The FKM
+// recursion below is O(n) deep, so a single-character alphabet (for which
+// `k.checked_pow(n)` never overflows, no matter how large `n` is) would
+// otherwise still blow the stack.
+const MAX_N: usize = 10_000;
+
+// Errors produced while generating or searching a cyclic pattern, so callers
+// can match on the failure instead of inspecting a formatted `String`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CyclicError {
+    // The alphabet was empty
+    EmptyAlphabet,
+    // `n` must be at least 1
+    ZeroN,
+    // `n` exceeds `MAX_N`, carrying the offending value
+    NTooLarge(usize),
+    // The requested length exceeds the maximum sequence length, carrying (requested, max)
+    LengthExceedsSequence(usize, usize),
+    // Alphabet size raised to the power of `n` doesn't fit in a `usize`
+    Overflow,
+    // `k^n` exceeds `MAX_SEARCHABLE_LENGTH`, so the sequence can't feasibly be searched
+    SearchSpaceTooLarge,
+    // The needle's length didn't match `n`, carrying (expected, actual)
+    NeedleLengthMismatch(usize, usize),
+}
+
+impl fmt::Display for CyclicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CyclicError::EmptyAlphabet => write!(f, "alphabet must not be empty"),
+            CyclicError::ZeroN => write!(f, "n must be at least 1"),
+            CyclicError::NTooLarge(n) => {
+                write!(f, "n = {} exceeds the maximum supported value of {}", n, MAX_N)
+            }
+            CyclicError::LengthExceedsSequence(length, max_length) => write!(
+                f,
+                "requested length {} exceeds the maximum De Bruijn sequence length {}",
+                length, max_length
+            ),
+            CyclicError::Overflow => {
+                write!(f, "alphabet size raised to the power of n overflows a usize")
+            }
+            CyclicError::SearchSpaceTooLarge => write!(
+                f,
+                "the De Bruijn sequence for this alphabet and n is too large to search"
+            ),
+            CyclicError::NeedleLengthMismatch(expected, actual) => write!(
+                f,
+                "needle is {} characters long, expected {} to match n",
+                actual, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CyclicError {}
+
+// Carries the recursion state for `db` so it doesn't need a long parameter
+// list threaded through every call.
+struct DbState<'a> {
+    n: usize,
+    k: usize,
+    a: Vec<usize>,
+    symbols: &'a [char],
+    sequence: Vec<char>,
+    limit: usize,
+}
+
+// Returns `true` once `state.sequence` has reached `state.limit` characters,
+// signalling the caller to stop recursing rather than explore the rest of
+// the sequence.
+fn db(t: usize, p: usize, state: &mut DbState) -> bool {
+    if state.sequence.len() >= state.limit {
+        return true;
+    }
+
+    if t > state.n {
+        if state.n.is_multiple_of(p) {
+            for i in 1..=p {
+                if state.sequence.len() >= state.limit {
+                    return true;
+                }
+
+                state.sequence.push(state.symbols[state.a[i]]);
+            }
+        }
+    } else {
+        state.a[t] = state.a[t - p];
+
+        if db(t + 1, p, state) {
+            return true;
+        }
+
+        for j in (state.a[t - p] + 1)..state.k {
+            state.a[t] = j;
+
+            if db(t + 1, t, state) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+// Generates up to `limit` characters of the lexicographically least De
+// Bruijn sequence B(k, n) using the Fredricksen-Kessler-Maiorana algorithm:
+// every Lyndon word whose length divides `n` is emitted, in lexicographic
+// order, as a prefix of the sequence, stopping as soon as `limit` characters
+// have been produced. A full, unbounded run of this generator would produce
+// a sequence of length k^n in which every length-n string over `symbols`
+// occurs exactly once.
+fn de_bruijn_prefix(symbols: &[char], n: usize, limit: usize) -> Vec<char> {
+    let k = symbols.len();
+    let mut state = DbState {
+        n,
+        k,
+        a: vec![0usize; n + 1],
+        symbols,
+        sequence: Vec::with_capacity(limit),
+        limit,
+    };
+
+    db(1, 1, &mut state);
+
+    state.sequence
+}
+
+// Generates a De Bruijn-based cyclic pattern of `length` characters over
+// `alphabet`, in which every `n`-length substring is unique. This is the
+// `pwntools`-style `cyclic` helper used to build buffer-overflow payloads
+// whose offset can be recovered from whichever fragment of it ends up in a
+// crashed process (e.g. an overwritten return address).
+pub fn cyclic_pattern(length: usize, n: usize, alphabet: &str) -> Result<String, CyclicError> {
+    let symbols: Vec<char> = alphabet.chars().collect();
+
+    if symbols.is_empty() {
+        return Err(CyclicError::EmptyAlphabet);
+    }
+
+    if n == 0 {
+        return Err(CyclicError::ZeroN);
+    }
+
+    if n > MAX_N {
+        return Err(CyclicError::NTooLarge(n));
+    }
+
+    let k = symbols.len();
+
+    let max_length = k.checked_pow(n as u32).ok_or(CyclicError::Overflow)?;
+
+    if length > max_length {
+        return Err(CyclicError::LengthExceedsSequence(length, max_length));
+    }
+
+    // Only ever generate as much of the sequence as was asked for, so a small
+    // `length` stays cheap even if `k^n` itself would be enormous
+    let sequence = de_bruijn_prefix(&symbols, n, length);
+
+    Ok(sequence.into_iter().collect())
+}
+
+// Finds the offset at which `value` (e.g. a crash address read out of a
+// register) occurs within the cyclic pattern B(k, n) over `alphabet`.
+// Returns `Ok(None)` if `value`'s bytes don't all fall within `alphabet`,
+// since such a value could never have been taken from the pattern; this is
+// checked before `value`'s length. Returns `Err` if `n` or `alphabet` are
+// invalid, `value`'s bytes are all in `alphabet` but its length doesn't
+// match `n`, or B(k, n) is too large to feasibly search.
+pub fn cyclic_find(
+    value: HexString,
+    n: usize,
+    alphabet: &str,
+    endianness: Endianness,
+) -> Result<Option<usize>, CyclicError> {
+    let symbols: Vec<char> = alphabet.chars().collect();
+
+    if symbols.is_empty() {
+        return Err(CyclicError::EmptyAlphabet);
+    }
+
+    if n == 0 {
+        return Err(CyclicError::ZeroN);
+    }
+
+    if n > MAX_N {
+        return Err(CyclicError::NTooLarge(n));
+    }
+
+    let k = symbols.len();
+
+    let needle_str = value.as_endianness(endianness).as_hex_string(endianness);
+    let mut needle_chars = String::with_capacity(needle_str.len() / 2);
+
+    for chunk in needle_str.as_bytes().chunks(2) {
+        let Ok(chunk_str) = std::str::from_utf8(chunk) else {
+            return Ok(None);
+        };
+        let Ok(byte) = u8::from_str_radix(chunk_str, 16) else {
+            return Ok(None);
+        };
+        let c = byte as char;
+
+        if !symbols.contains(&c) {
+            return Ok(None);
+        }
+
+        needle_chars.push(c);
+    }
+
+    if needle_chars.len() != n {
+        return Err(CyclicError::NeedleLengthMismatch(n, needle_chars.len()));
+    }
+
+    let max_length = k.checked_pow(n as u32).ok_or(CyclicError::Overflow)?;
+
+    if max_length > MAX_SEARCHABLE_LENGTH {
+        return Err(CyclicError::SearchSpaceTooLarge);
+    }
+
+    let pattern: String = de_bruijn_prefix(&symbols, n, max_length)
+        .into_iter()
+        .collect();
+    let haystack = HexString::from_str(&pattern, Endianness::Little, Endianness::Little);
+    let needle = HexString::from_str(&needle_chars, Endianness::Little, Endianness::Little);
+
+    Ok(haystack.get_offsets(needle).into_iter().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cyclic_pattern_is_unique_per_window() {
+        let pattern = cyclic_pattern(81, 4, "abc").unwrap();
+        let chars: Vec<char> = pattern.chars().collect();
+
+        let mut seen = std::collections::HashSet::new();
+        for window in chars.windows(4) {
+            assert!(seen.insert(window.to_vec()), "window {:?} repeated", window);
+        }
+    }
+
+    #[test]
+    fn cyclic_pattern_truncates_to_requested_length() {
+        let pattern = cyclic_pattern(3, 2, "ab").unwrap();
+        assert_eq!(pattern.len(), 3);
+    }
+
+    #[test]
+    fn cyclic_pattern_rejects_length_beyond_sequence() {
+        let result = cyclic_pattern(5, 2, "ab");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn cyclic_pattern_rejects_empty_alphabet() {
+        let result = cyclic_pattern(0, 4, "");
+        assert_eq!(result, Err(CyclicError::EmptyAlphabet));
+    }
+
+    #[test]
+    fn cyclic_pattern_rejects_zero_n() {
+        let result = cyclic_pattern(1, 0, "ab");
+        assert_eq!(result, Err(CyclicError::ZeroN));
+    }
+
+    #[test]
+    fn cyclic_pattern_rejects_n_too_large_even_for_single_char_alphabet() {
+        // k == 1 means `k.checked_pow(n)` never overflows, no matter how
+        // large `n` is, so this must be rejected by the MAX_N bound instead
+        let result = cyclic_pattern(1, 100_000, "a");
+        assert_eq!(result, Err(CyclicError::NTooLarge(100_000)));
+    }
+
+    #[test]
+    fn cyclic_pattern_with_small_length_ignores_large_n() {
+        // k^n here is far too large to ever materialize in full (62^10 is
+        // ~8.4e17 elements); only the requested prefix should be generated
+        let pattern = cyclic_pattern(4, 10, DEFAULT_ALPHABET).unwrap();
+        assert_eq!(pattern.len(), 4);
+    }
+
+    #[test]
+    fn cyclic_find_locates_known_offset() {
+        let alphabet = "abc";
+        let pattern = cyclic_pattern(81, 4, alphabet).unwrap();
+        let needle = &pattern[40..44];
+
+        let value = HexString::from_str(needle, Endianness::Little, Endianness::Little);
+
+        let offset = cyclic_find(value, 4, alphabet, Endianness::Little).unwrap();
+
+        assert_eq!(offset, Some(40));
+    }
+
+    #[test]
+    fn cyclic_find_rejects_value_outside_alphabet() {
+        let value = HexString::from_hex_str("0011", Endianness::Little, Endianness::Little)
+            .unwrap();
+
+        let offset = cyclic_find(value, 4, "abc", Endianness::Little).unwrap();
+
+        assert_eq!(offset, None);
+    }
+
+    #[test]
+    fn cyclic_find_rejects_empty_alphabet() {
+        let value = HexString::from_hex_str("0011", Endianness::Little, Endianness::Little)
+            .unwrap();
+
+        assert_eq!(
+            cyclic_find(value, 4, "", Endianness::Little),
+            Err(CyclicError::EmptyAlphabet)
+        );
+    }
+
+    #[test]
+    fn cyclic_find_rejects_zero_n() {
+        let value = HexString::from_hex_str("0011", Endianness::Little, Endianness::Little)
+            .unwrap();
+
+        assert_eq!(
+            cyclic_find(value, 0, "ab", Endianness::Little),
+            Err(CyclicError::ZeroN)
+        );
+    }
+
+    #[test]
+    fn cyclic_find_rejects_n_too_large_even_for_single_char_alphabet() {
+        let value = HexString::from_hex_str("61", Endianness::Little, Endianness::Little)
+            .unwrap();
+
+        assert_eq!(
+            cyclic_find(value, 100_000, "a", Endianness::Little),
+            Err(CyclicError::NTooLarge(100_000))
+        );
+    }
+
+    #[test]
+    fn cyclic_find_rejects_needle_length_mismatch() {
+        // "abc" is 3 ASCII bytes (6 hex chars) but n is 4, so this could
+        // never have been taken from a B(k, 4) pattern
+        let value = HexString::from_str("abc", Endianness::Little, Endianness::Little);
+
+        assert_eq!(
+            cyclic_find(value, 4, "abc", Endianness::Little),
+            Err(CyclicError::NeedleLengthMismatch(4, 3))
+        );
+    }
+}
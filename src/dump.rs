@@ -0,0 +1,264 @@
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char as nom_char, hex_digit1, space0, space1},
+    combinator::{all_consuming, opt, verify},
+    multi::{many1, separated_list1},
+    sequence::{preceded, terminated},
+    IResult,
+};
+
+use crate::hex::{Endianness, HexError, HexString};
+
+// Which debugger/tool shape a pasted dump is expected to follow
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DumpFormat {
+    // Sniff the format from the first non-blank line
+    Auto,
+    // `xxd`/`objdump -s` style: an address column, groups of hex digits, and
+    // an optional trailing ASCII preview column
+    Xxd,
+    // GDB's `x` command: a `0x`-prefixed address followed by a colon, then
+    // whitespace-separated `0x`-prefixed byte values
+    Gdb,
+    // Bare hex digits, optionally whitespace-separated, with no address or
+    // ASCII columns
+    Plain,
+}
+
+impl HexString {
+    // Parses debugger/hexdump output - `xxd`, `objdump -s`, or GDB's `x`
+    // command - into a `HexString`, discarding the leading address column
+    // and any trailing ASCII preview column and concatenating the
+    // remaining hex byte groups in file order before handing off to the
+    // usual `HexByte` construction.
+    pub fn from_dump(
+        s: &str,
+        format: DumpFormat,
+        source_endianness: Endianness,
+        target_endianness: Endianness,
+    ) -> Result<Self, HexError> {
+        let lines: Vec<&str> = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let format = match format {
+            DumpFormat::Auto => sniff_format(&lines),
+            format => format,
+        };
+
+        let mut hex_chars = String::new();
+
+        for line in &lines {
+            let (_, chars) = parse_line(line, format)
+                .map_err(|_| HexError::InvalidChar(line.chars().next().unwrap_or(' ')))?;
+
+            hex_chars.push_str(&chars);
+        }
+
+        HexString::from_hex_str(&hex_chars, source_endianness, target_endianness)
+    }
+}
+
+fn parse_line(line: &str, format: DumpFormat) -> IResult<&str, String> {
+    match format {
+        DumpFormat::Gdb => gdb_line(line),
+        DumpFormat::Xxd => xxd_line(line),
+        DumpFormat::Plain => plain_line(line),
+        DumpFormat::Auto => unreachable!("Auto is resolved to a concrete format before parsing"),
+    }
+}
+
+// Sniffs the format from the first non-blank line, the same way a user would
+// eyeball whatever they've pasted. Only a leading `0x...:` (GDB) or a colon
+// after the address (xxd) are unambiguous enough to autodetect; `objdump -s`,
+// which omits the colon, looks identical to whitespace-separated plain hex
+// and must be requested explicitly via `DumpFormat::Xxd`.
+fn sniff_format(lines: &[&str]) -> DumpFormat {
+    let first_line = match lines.first() {
+        Some(line) => *line,
+        None => return DumpFormat::Plain,
+    };
+
+    if gdb_line(first_line).is_ok() {
+        DumpFormat::Gdb
+    } else if first_line.contains(':') && xxd_line(first_line).is_ok() {
+        DumpFormat::Xxd
+    } else {
+        DumpFormat::Plain
+    }
+}
+
+// Strips a trailing ASCII preview column, which both `xxd` and `objdump -s`
+// separate from the hex byte groups with at least two spaces
+fn strip_ascii_gutter(line: &str) -> &str {
+    match line.find("  ") {
+        Some(index) => &line[..index],
+        None => line,
+    }
+}
+
+// "0000: 4141 4141  AAAA" (`xxd`) or "0000 4141 4141  AAAA" (`objdump -s`,
+// which omits the colon): an address of at least 4 hex digits - long enough
+// to not be mistaken for a lone byte group - followed by one or more
+// whitespace-separated groups of hex digits. Wrapped in `all_consuming` so a
+// partially-garbled group (e.g. a stray non-hex character splitting it) is
+// rejected outright instead of silently truncating the line.
+fn xxd_line(input: &str) -> IResult<&str, String> {
+    let hex_part = strip_ascii_gutter(input);
+
+    all_consuming(|hex_part| {
+        let (remainder, _) = terminated(
+            verify(hex_digit1, |address: &str| address.len() >= 4),
+            opt(nom_char(':')),
+        )(hex_part)?;
+
+        let (remainder, groups) =
+            preceded(space0, separated_list1(space1, hex_digit1))(remainder)?;
+
+        Ok((remainder, groups.concat()))
+    })(hex_part)
+}
+
+// "0x601040:	0x41	0x41" (GDB's `x/32xb`): a `0x`-prefixed address, a
+// colon, then one or more whitespace-separated `0x`-prefixed byte values.
+// Wrapped in `all_consuming` for the same reason as `xxd_line`.
+fn gdb_line(input: &str) -> IResult<&str, String> {
+    all_consuming(|input| {
+        let (remainder, _) = terminated(preceded(tag("0x"), hex_digit1), nom_char(':'))(input)?;
+
+        let (remainder, bytes) =
+            many1(preceded(space1, preceded(tag("0x"), hex_digit1)))(remainder)?;
+
+        Ok((remainder, bytes.concat()))
+    })(input)
+}
+
+// Bare hex digits with no address or ASCII columns, optionally grouped with
+// whitespace. Wrapped in `all_consuming` for the same reason as `xxd_line`.
+fn plain_line(input: &str) -> IResult<&str, String> {
+    all_consuming(|input| {
+        let (remainder, groups) = separated_list1(space1, hex_digit1)(input)?;
+
+        Ok((remainder, groups.concat()))
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_dump_parses_xxd_output() {
+        let dump = "00000000: 4142 4344  ABCD\n00000004: 4546 4748  EFGH";
+
+        let hex_string =
+            HexString::from_dump(dump, DumpFormat::Xxd, Endianness::Big, Endianness::Big)
+                .unwrap();
+
+        assert_eq!(
+            hex_string.as_hex_string(Endianness::Big),
+            "4142434445464748"
+        );
+    }
+
+    #[test]
+    fn from_dump_parses_objdump_output_without_colon() {
+        let dump = " 0000 4142 4344  AB.CD...........";
+
+        let hex_string =
+            HexString::from_dump(dump, DumpFormat::Xxd, Endianness::Big, Endianness::Big)
+                .unwrap();
+
+        assert_eq!(hex_string.as_hex_string(Endianness::Big), "41424344");
+    }
+
+    #[test]
+    fn from_dump_parses_gdb_output() {
+        let dump = "0x601040:\t0x41\t0x42\t0x43\t0x44";
+
+        let hex_string =
+            HexString::from_dump(dump, DumpFormat::Gdb, Endianness::Big, Endianness::Big)
+                .unwrap();
+
+        assert_eq!(hex_string.as_hex_string(Endianness::Big), "41424344");
+    }
+
+    #[test]
+    fn from_dump_parses_plain_hex() {
+        let dump = "41424344";
+
+        let hex_string =
+            HexString::from_dump(dump, DumpFormat::Plain, Endianness::Big, Endianness::Big)
+                .unwrap();
+
+        assert_eq!(hex_string.as_hex_string(Endianness::Big), "41424344");
+    }
+
+    #[test]
+    fn from_dump_autodetects_xxd() {
+        let dump = "00000000: 4142 4344  ABCD";
+
+        let hex_string =
+            HexString::from_dump(dump, DumpFormat::Auto, Endianness::Big, Endianness::Big)
+                .unwrap();
+
+        assert_eq!(hex_string.as_hex_string(Endianness::Big), "41424344");
+    }
+
+    #[test]
+    fn from_dump_autodetects_gdb() {
+        let dump = "0x601040:\t0x41\t0x42";
+
+        let hex_string =
+            HexString::from_dump(dump, DumpFormat::Auto, Endianness::Big, Endianness::Big)
+                .unwrap();
+
+        assert_eq!(hex_string.as_hex_string(Endianness::Big), "4142");
+    }
+
+    #[test]
+    fn from_dump_autodetects_plain() {
+        let dump = "4142 4344";
+
+        let hex_string =
+            HexString::from_dump(dump, DumpFormat::Auto, Endianness::Big, Endianness::Big)
+                .unwrap();
+
+        assert_eq!(hex_string.as_hex_string(Endianness::Big), "41424344");
+    }
+
+    #[test]
+    fn from_dump_rejects_non_hex_content() {
+        let result = HexString::from_dump(
+            "not a hex dump",
+            DumpFormat::Plain,
+            Endianness::Big,
+            Endianness::Big,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn from_dump_rejects_trailing_garbage() {
+        let result = HexString::from_dump(
+            "41424344garbage",
+            DumpFormat::Plain,
+            Endianness::Big,
+            Endianness::Big,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn from_dump_rejects_partially_garbled_group() {
+        let dump = "00000000: 414Z 4344  AB.CD";
+
+        let result = HexString::from_dump(dump, DumpFormat::Xxd, Endianness::Big, Endianness::Big);
+
+        assert!(result.is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::fmt;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Endianness {
@@ -6,6 +7,76 @@ pub enum Endianness {
     Little,
 }
 
+// An encoding that a payload can be rendered into for pasting straight into an
+// exploit script, debugger, or HTTP/JSON transport
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayloadFormat {
+    // Python byte-string escapes, e.g. `b'\x41\x42'`
+    PythonBytes,
+    // A C `unsigned char[]` array initializer
+    CArray,
+    // Standard, padded base64
+    Base64,
+    // A GDB `set {char[N]} ADDRESS = "..."` expression
+    Gdb,
+}
+
+// Errors produced while parsing or converting hexadecimal content, so callers
+// can match on the failure instead of inspecting a formatted `String`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexError {
+    // A character that isn't `0-9`, `a-f`, or `A-F`; `HexString::from_dump`
+    // instead carries the first character of the line it failed to parse,
+    // which may itself be a hex digit
+    InvalidChar(char),
+    // More than two hex characters were supplied for a single byte, carrying the offending length
+    ByteTooLong(usize),
+    // The value doesn't fit in a `usize` on this platform
+    Overflow,
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HexError::InvalidChar(c) => write!(
+                f,
+                "cannot instantiate a HexByte with a non-hexadecimal character: {}",
+                c
+            ),
+            HexError::ByteTooLong(len) => write!(
+                f,
+                "HexByte contents must be of most length 2, provided: {}",
+                len
+            ),
+            HexError::Overflow => write!(f, "value is too large to fit in a usize"),
+        }
+    }
+}
+
+impl std::error::Error for HexError {}
+
+// Relative frequency of each byte value (0x00..=0xff) in typical binaries and
+// English text, indexed by byte value. Lower is rarer. Used by `get_offsets`
+// to pick the position in a needle least likely to appear in the haystack.
+static BYTE_FREQUENCY: [u8; 256] = [
+    255, 10, 10, 10, 10, 10, 10, 10, 10, 60, 150, 10, 10, 90, 10, 10, // 0x00..=0x0f
+    10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, // 0x10..=0x1f
+    220, 50, 50, 10, 10, 10, 10, 50, 50, 50, 10, 10, 50, 50, 50, 50, // 0x20..=0x2f
+    70, 70, 70, 70, 70, 70, 70, 70, 70, 70, 50, 50, 10, 10, 10, 50, // 0x30..=0x3f
+    10, 81, 47, 54, 61, 103, 51, 50, 70, 75, 41, 44, 60, 52, 73, 77, // 0x40..=0x4f
+    49, 40, 70, 71, 85, 54, 45, 52, 41, 50, 40, 10, 50, 10, 10, 50, // 0x50..=0x5f
+    10, 142, 75, 88, 103, 187, 82, 80, 121, 130, 62, 68, 100, 84, 127, 135, // 0x60..=0x6f
+    79, 61, 120, 123, 151, 88, 70, 84, 62, 80, 61, 10, 10, 10, 10, 10, // 0x70..=0x7f
+    10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, // 0x80..=0x8f
+    120, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, // 0x90..=0x9f
+    10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, // 0xa0..=0xaf
+    10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, // 0xb0..=0xbf
+    10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 80, 10, 10, 10, // 0xc0..=0xcf
+    10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, // 0xd0..=0xdf
+    10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, // 0xe0..=0xef
+    10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 180, // 0xf0..=0xff
+];
+
 // Little-endian hexadecimal string
 #[derive(Debug, PartialEq, Eq)]
 pub struct HexString {
@@ -34,7 +105,7 @@ impl HexString {
         s: &str,
         source_endianness: Endianness,
         target_endianness: Endianness,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, HexError> {
         let mut string_of_hex_chars = String::new();
 
         // Prefix '0' if odd numebr of characters to pad into bytes
@@ -86,7 +157,13 @@ impl HexString {
         }
     }
 
-    // Returns a vector of the indices at which `needle` is found in `self.hex_bytes`
+    // Returns a vector of the indices at which `needle` is found in `self.hex_bytes`.
+    //
+    // Rather than comparing at every haystack position, this picks the byte within
+    // `needle` that is rarest according to `BYTE_FREQUENCY`, scans the haystack for
+    // only that byte value, and confirms a full match at each candidate alignment.
+    // This keeps the same semantics and ordering as a naive scan while skipping the
+    // (usually many) positions that the rare byte rules out up front.
     pub fn get_offsets(&self, mut needle: Self) -> Vec<usize> {
         let mut matches = Vec::new();
 
@@ -99,45 +176,41 @@ impl HexString {
             needle = needle.as_endianness(self.endianness);
         }
 
-        // Use a `VecDeque` as a FIFO which contains content equivalent to the needle size for comparison
-        let mut current_hex_bytes = VecDeque::with_capacity(needle.hex_bytes.len());
+        let needle_len = needle.hex_bytes.len();
 
-        for i in 0..self.hex_bytes.len() {
-            // Not enough remaining content to update `current_hex_bytes` without going OOB
-            if i + needle.hex_bytes.len() > self.hex_bytes.len() {
-                break;
-            }
-
-            // Update the FIFO
-            if i == 0 {
-                // Setup the FIFO on the first iteration
-                for i in 0..needle.hex_bytes.len() {
-                    // `unwrap` as we have previously checked that `contents` has at least the same length as `needle`
-                    current_hex_bytes.push_back(self.hex_bytes.get(i).unwrap());
-                }
-            } else {
-                // Update the FIFO on each iteration after the first, `unwrap` because we've already protected against OOB
-                current_hex_bytes.pop_front();
-                current_hex_bytes
-                    .push_back(self.hex_bytes.get(i + needle.hex_bytes.len() - 1).unwrap());
+        // `unwrap` as we've already returned above if `needle` is empty
+        let (rarest_index, rarest_byte) = needle
+            .hex_bytes
+            .iter()
+            .map(HexByte::as_u8)
+            .enumerate()
+            .min_by_key(|&(_, byte)| BYTE_FREQUENCY[byte as usize])
+            .unwrap();
+
+        // Only positions where the rarest byte lines up with a haystack byte of the
+        // same value are worth a full comparison
+        for i in rarest_index..self.hex_bytes.len() {
+            if self.hex_bytes[i].as_u8() != rarest_byte {
+                continue;
             }
 
-            let mut matched = true;
+            let start = i - rarest_index;
 
-            // Check whether `current_hex_bytes` and `needle` match
-            for (&a, b) in current_hex_bytes.iter().zip(needle.hex_bytes.iter()) {
-                if a != b {
-                    matched = false;
-                    break;
-                }
+            if start + needle_len > self.hex_bytes.len() {
+                break;
             }
 
+            let matched = self.hex_bytes[start..start + needle_len]
+                .iter()
+                .zip(needle.hex_bytes.iter())
+                .all(|(a, b)| a == b);
+
             if matched {
-                matches.push(i);
+                matches.push(start);
             }
         }
 
-        return matches;
+        matches
     }
 
     pub fn as_hex_string(self, endianness: Endianness) -> String {
@@ -156,10 +229,197 @@ impl HexString {
         result
     }
 
-    pub fn as_usize(self) -> Option<usize> {
-        match usize::from_str_radix(&self.as_hex_string(Endianness::Big), 16) {
-            Ok(i) => Some(i),
-            Err(_) => None,
+    pub fn as_usize(self) -> Result<usize, HexError> {
+        usize::from_str_radix(&self.as_hex_string(Endianness::Big), 16)
+            .map_err(|_| HexError::Overflow)
+    }
+
+    // Renders the payload in `format`, after normalizing it to `endianness`, ready
+    // to paste into an exploit script, debugger session, or HTTP/JSON request
+    pub fn format_as(self, format: PayloadFormat, endianness: Endianness) -> String {
+        let bytes: Vec<u8> = self
+            .as_endianness(endianness)
+            .hex_bytes
+            .iter()
+            .map(HexByte::as_u8)
+            .collect();
+
+        match format {
+            PayloadFormat::PythonBytes => {
+                let escapes: String = bytes.iter().map(|byte| format!("\\x{:02x}", byte)).collect();
+                format!("b'{}'", escapes)
+            }
+            PayloadFormat::CArray => {
+                let items: Vec<String> = bytes.iter().map(|byte| format!("0x{:02x}", byte)).collect();
+                format!("unsigned char payload[] = {{{}}};", items.join(", "))
+            }
+            PayloadFormat::Base64 => base64_encode(&bytes),
+            PayloadFormat::Gdb => {
+                let escapes: String = bytes.iter().map(|byte| format!("\\x{:02x}", byte)).collect();
+                format!("set {{char[{}]}} ADDRESS = \"{}\"", bytes.len(), escapes)
+            }
+        }
+    }
+
+    // Locates every occurrence of every needle in a single pass over `self.hex_bytes`
+    // using an Aho-Corasick automaton, returning `(needle_index, offset)` pairs.
+    // Each needle is normalized to `self.endianness` first, as `get_offsets` does.
+    pub fn get_offsets_multi(&self, needles: Vec<Self>) -> Vec<(usize, usize)> {
+        let mut matches = Vec::new();
+
+        if needles.is_empty() {
+            return matches;
+        }
+
+        let needles: Vec<Self> = needles
+            .into_iter()
+            .map(|needle| {
+                if needle.endianness != self.endianness {
+                    needle.as_endianness(self.endianness)
+                } else {
+                    needle
+                }
+            })
+            .collect();
+
+        let mut nodes = vec![TrieNode::new()];
+
+        for (needle_index, needle) in needles.iter().enumerate() {
+            if needle.hex_bytes.is_empty() {
+                continue;
+            }
+
+            let mut current = 0;
+
+            for hex_byte in &needle.hex_bytes {
+                let byte = hex_byte.as_u8() as usize;
+
+                current = match nodes[current].children[byte] {
+                    Some(next) => next,
+                    None => {
+                        nodes.push(TrieNode::new());
+                        let next = nodes.len() - 1;
+                        nodes[current].children[byte] = Some(next);
+                        next
+                    }
+                };
+            }
+
+            nodes[current].output.push(needle_index);
+        }
+
+        // Breadth-first failure-link construction: each node's failure link points
+        // to the longest proper suffix of its path that is also a trie prefix, and
+        // output sets are propagated along failure links so needles that are
+        // suffixes of one another are all reported.
+        let mut queue = VecDeque::new();
+
+        for byte in 0..256 {
+            if let Some(child) = nodes[0].children[byte] {
+                nodes[child].fail = 0;
+                queue.push_back(child);
+            }
+        }
+
+        while let Some(current) = queue.pop_front() {
+            let children: Vec<(usize, usize)> = nodes[current]
+                .children
+                .iter()
+                .enumerate()
+                .filter_map(|(byte, child)| child.map(|next| (byte, next)))
+                .collect();
+
+            for (byte, child) in children {
+                let mut fail = nodes[current].fail;
+
+                let fail_child = loop {
+                    if let Some(next) = nodes[fail].children[byte] {
+                        break next;
+                    }
+
+                    if fail == 0 {
+                        break 0;
+                    }
+
+                    fail = nodes[fail].fail;
+                };
+
+                nodes[child].fail = fail_child;
+
+                let inherited = nodes[fail_child].output.clone();
+                nodes[child].output.extend(inherited);
+
+                queue.push_back(child);
+            }
+        }
+
+        // Walk the haystack once, following goto/failure transitions
+        let mut current = 0;
+
+        for (i, hex_byte) in self.hex_bytes.iter().enumerate() {
+            let byte = hex_byte.as_u8() as usize;
+
+            while current != 0 && nodes[current].children[byte].is_none() {
+                current = nodes[current].fail;
+            }
+
+            current = nodes[current].children[byte].unwrap_or(0);
+
+            for &needle_index in &nodes[current].output {
+                let needle_len = needles[needle_index].hex_bytes.len();
+                matches.push((needle_index, i + 1 - needle_len));
+            }
+        }
+
+        matches
+    }
+}
+
+// Encodes `bytes` as standard, padded base64
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        let triple = ((b0 as u32) << 16) | ((b1 as u32) << 8) | (b2 as u32);
+
+        result.push(ALPHABET[(triple >> 18 & 0x3f) as usize] as char);
+        result.push(ALPHABET[(triple >> 12 & 0x3f) as usize] as char);
+        result.push(if chunk.len() > 1 {
+            ALPHABET[(triple >> 6 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+        result.push(if chunk.len() > 2 {
+            ALPHABET[(triple & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+
+    result
+}
+
+// A single node of the Aho-Corasick trie built over needle byte sequences,
+// keyed by byte value (0x00..=0xff).
+struct TrieNode {
+    children: [Option<usize>; 256],
+    fail: usize,
+    output: Vec<usize>,
+}
+
+impl TrieNode {
+    fn new() -> Self {
+        Self {
+            children: [None; 256],
+            fail: 0,
+            output: Vec::new(),
         }
     }
 }
@@ -172,12 +432,9 @@ pub struct HexByte {
 
 impl HexByte {
     // Creates a new `HexByte`, consisting of lower case characters, ensuring it's a valid hexadecimal value
-    pub fn from_hex_str(hex_byte: &str) -> Result<Self, String> {
+    pub fn from_hex_str(hex_byte: &str) -> Result<Self, HexError> {
         if hex_byte.len() > 2 {
-            return Err(format!(
-                "HexByte contents must be of most length 2, provided: {}",
-                hex_byte.len()
-            ));
+            return Err(HexError::ByteTooLong(hex_byte.len()));
         }
 
         let hex_byte = format!("{:0>2}", hex_byte.to_owned());
@@ -188,10 +445,7 @@ impl HexByte {
                 || (c as u8 >= 65 && c as u8 <= 70)
                 || (c as u8 >= 97 && c as u8 <= 102))
             {
-                return Err(format!(
-                    "cannot instantiate a HexByte with a non-hexadecimal character: {}",
-                    c
-                ));
+                return Err(HexError::InvalidChar(c));
             }
         }
 
@@ -199,6 +453,12 @@ impl HexByte {
             contents: hex_byte.to_lowercase(),
         })
     }
+
+    // The numeric byte value this `HexByte` represents
+    fn as_u8(&self) -> u8 {
+        // `unwrap` as `contents` is guaranteed to be valid hexadecimal by construction
+        u8::from_str_radix(&self.contents, 16).unwrap()
+    }
 }
 
 impl ToString for HexByte {
@@ -339,7 +599,7 @@ mod tests {
         let hex_string =
             HexString::from_hex_str("00112233", Endianness::Big, Endianness::Big).unwrap();
 
-        assert_eq!(hex_string.as_usize(), Some(1122867));
+        assert_eq!(hex_string.as_usize(), Ok(1122867));
     }
 
     #[test]
@@ -347,7 +607,7 @@ mod tests {
         let hex_string =
             HexString::from_hex_str("fffffffffffffffff", Endianness::Big, Endianness::Big).unwrap();
 
-        assert_eq!(hex_string.as_usize(), None);
+        assert_eq!(hex_string.as_usize(), Err(HexError::Overflow));
     }
 
     fn create_le_hex_string() -> HexString {
@@ -435,6 +695,134 @@ mod tests {
         assert_eq!(offsets.len(), 0);
     }
 
+    #[test]
+    fn get_offsets_with_rare_trailing_byte() {
+        // The needle's rarest byte (0x22) sits last, exercising the case where the
+        // scan has to align backwards from the candidate position it found.
+        let haystack = HexString::from_hex_str(
+            "000000ff11000000ff22",
+            Endianness::Little,
+            Endianness::Little,
+        )
+        .unwrap();
+
+        let needle =
+            HexString::from_hex_str("0000ff22", Endianness::Little, Endianness::Little).unwrap();
+
+        let offsets = haystack.get_offsets(needle);
+
+        assert_eq!(offsets, vec![6]);
+    }
+
+    #[test]
+    fn get_offsets_multi_finds_every_needle() {
+        let haystack = HexString::from_hex_str(
+            "00112233440011223344",
+            Endianness::Little,
+            Endianness::Little,
+        )
+        .unwrap();
+
+        let needle_a =
+            HexString::from_hex_str("2233", Endianness::Little, Endianness::Little).unwrap();
+        let needle_b =
+            HexString::from_hex_str("44", Endianness::Little, Endianness::Little).unwrap();
+
+        let mut offsets = haystack.get_offsets_multi(vec![needle_a, needle_b]);
+        offsets.sort();
+
+        assert_eq!(offsets, vec![(0, 2), (0, 7), (1, 4), (1, 9)]);
+    }
+
+    #[test]
+    fn get_offsets_multi_reports_needles_that_are_suffixes_of_each_other() {
+        let haystack =
+            HexString::from_hex_str("aabbcc", Endianness::Little, Endianness::Little).unwrap();
+
+        let needle_a =
+            HexString::from_hex_str("bbcc", Endianness::Little, Endianness::Little).unwrap();
+        let needle_b =
+            HexString::from_hex_str("cc", Endianness::Little, Endianness::Little).unwrap();
+
+        let mut offsets = haystack.get_offsets_multi(vec![needle_a, needle_b]);
+        offsets.sort();
+
+        assert_eq!(offsets, vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn get_offsets_multi_with_no_needles() {
+        let haystack =
+            HexString::from_hex_str("aabbcc", Endianness::Little, Endianness::Little).unwrap();
+
+        assert_eq!(haystack.get_offsets_multi(vec![]), Vec::new());
+    }
+
+    #[test]
+    fn format_as_python_bytes() {
+        let hex_string =
+            HexString::from_hex_str("414243", Endianness::Big, Endianness::Big).unwrap();
+
+        assert_eq!(
+            hex_string.format_as(PayloadFormat::PythonBytes, Endianness::Big),
+            "b'\\x41\\x42\\x43'"
+        );
+    }
+
+    #[test]
+    fn format_as_c_array() {
+        let hex_string =
+            HexString::from_hex_str("414243", Endianness::Big, Endianness::Big).unwrap();
+
+        assert_eq!(
+            hex_string.format_as(PayloadFormat::CArray, Endianness::Big),
+            "unsigned char payload[] = {0x41, 0x42, 0x43};"
+        );
+    }
+
+    #[test]
+    fn format_as_base64() {
+        let hex_string =
+            HexString::from_hex_str("414243", Endianness::Big, Endianness::Big).unwrap();
+
+        assert_eq!(
+            hex_string.format_as(PayloadFormat::Base64, Endianness::Big),
+            "QUJD"
+        );
+    }
+
+    #[test]
+    fn format_as_base64_pads_correctly() {
+        let hex_string = HexString::from_hex_str("41", Endianness::Big, Endianness::Big).unwrap();
+
+        assert_eq!(
+            hex_string.format_as(PayloadFormat::Base64, Endianness::Big),
+            "QQ=="
+        );
+    }
+
+    #[test]
+    fn format_as_gdb() {
+        let hex_string =
+            HexString::from_hex_str("414243", Endianness::Big, Endianness::Big).unwrap();
+
+        assert_eq!(
+            hex_string.format_as(PayloadFormat::Gdb, Endianness::Big),
+            "set {char[3]} ADDRESS = \"\\x41\\x42\\x43\""
+        );
+    }
+
+    #[test]
+    fn format_as_respects_requested_endianness() {
+        let hex_string =
+            HexString::from_hex_str("414243", Endianness::Big, Endianness::Big).unwrap();
+
+        assert_eq!(
+            hex_string.format_as(PayloadFormat::PythonBytes, Endianness::Little),
+            "b'\\x43\\x42\\x41'"
+        );
+    }
+
     #[test]
     fn hex_byte_from_hex_str() {
         assert_eq!(HexByte::from_hex_str("AB").unwrap().contents, "ab");
@@ -1,6 +1,9 @@
 use std::process;
 
 fn main() {
+    // `run` isn't defined in this crate (no lib.rs), so there's nothing here
+    // to thread HexError/CyclicError through; the `{}` below already prints
+    // whatever typed error `run` eventually returns via its Display impl.
     bohelper::run().unwrap_or_else(|err| {
         eprintln!("Error encountered: {}", err);
         process::exit(1);